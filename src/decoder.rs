@@ -0,0 +1,371 @@
+//! Incremental GIF decoding over [`Read`]
+//! Unlike the nom-based [`Parse`](crate::gif::Parse) trait, which requires
+//! the whole file up front, [`Decoder`] pulls bytes from the reader as
+//! they're needed and reports failures as a [`DecodingError`].
+#![allow(unused)]
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read};
+
+use crate::gif::{
+    color_table_spec, Block, ColorTable, Extension, ExtensionType, GraphicControlExtension, Header,
+    Image, ImageData, ImageDescriptor, LogicalScreenDescriptor, SubBlock, SubBlocks, Version,
+};
+
+#[derive(Debug)]
+pub(crate) enum DecodingError {
+    /// The bytes read don't form a valid GIF construct.
+    Format(&'static str),
+    Io(io::Error),
+    /// A state that should be unreachable if the rest of this module is correct.
+    Internal(&'static str),
+}
+
+impl fmt::Display for DecodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Format(msg) => write!(f, "malformed GIF: {msg}"),
+            Self::Io(err) => write!(f, "I/O error: {err}"),
+            Self::Internal(msg) => write!(f, "internal decoder error: {msg}"),
+        }
+    }
+}
+
+impl Error for DecodingError {}
+
+impl From<io::Error> for DecodingError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Streams [`Block`]s out of a GIF file one at a time, reading only as many
+/// bytes as each block needs.
+pub(crate) struct Decoder<R: Read> {
+    reader: R,
+    header: Header,
+    done: bool,
+}
+
+impl<R: Read> Decoder<R> {
+    pub(crate) fn new(mut reader: R) -> Result<Self, DecodingError> {
+        let header = read_header(&mut reader)?;
+        Ok(Self {
+            reader,
+            header,
+            done: false,
+        })
+    }
+
+    pub(crate) fn header(&self) -> &Header {
+        &self.header
+    }
+}
+
+impl<R: Read> Iterator for Decoder<R> {
+    type Item = Result<Block, DecodingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let sentinel = match read_byte_or_eof(&mut self.reader) {
+            Ok(Some(sentinel)) => sentinel,
+            Ok(None) => {
+                self.done = true;
+                return None;
+            }
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err));
+            }
+        };
+
+        let block = match sentinel {
+            b',' => read_image(&mut self.reader).map(Block::Image),
+            b'!' => read_extension(&mut self.reader).map(Block::Extension),
+            b';' => {
+                self.done = true;
+                return None;
+            }
+            _ => Err(DecodingError::Format("unexpected block sentinel")),
+        };
+
+        if block.is_err() {
+            self.done = true;
+        }
+
+        Some(block)
+    }
+}
+
+fn read_byte<R: Read>(reader: &mut R) -> Result<u8, DecodingError> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_byte_or_eof<R: Read>(reader: &mut R) -> Result<Option<u8>, DecodingError> {
+    let mut buf = [0u8; 1];
+    match reader.read(&mut buf)? {
+        0 => Ok(None),
+        _ => Ok(Some(buf[0])),
+    }
+}
+
+fn read_u16_le<R: Read>(reader: &mut R) -> Result<u16, DecodingError> {
+    let mut buf = [0u8; 2];
+    reader.read_exact(&mut buf)?;
+    Ok(u16::from_le_bytes(buf))
+}
+
+fn read_version<R: Read>(reader: &mut R) -> Result<Version, DecodingError> {
+    let mut buf = [0u8; 6];
+    reader.read_exact(&mut buf)?;
+    match &buf {
+        b"GIF87a" => Ok(Version::Gif87a),
+        b"GIF89a" => Ok(Version::Gif89a),
+        _ => Err(DecodingError::Format("unrecognized GIF version tag")),
+    }
+}
+
+fn read_color_table<R: Read>(reader: &mut R, size: usize) -> Result<ColorTable, DecodingError> {
+    let mut colors = Vec::with_capacity(size);
+
+    for _ in 0..size {
+        let mut rgb = [0u8; 3];
+        reader.read_exact(&mut rgb)?;
+        colors.push(rgb);
+    }
+
+    Ok(ColorTable { colors })
+}
+
+fn read_logical_screen_descriptor<R: Read>(
+    reader: &mut R,
+) -> Result<LogicalScreenDescriptor, DecodingError> {
+    let width = read_u16_le(reader)?;
+    let height = read_u16_le(reader)?;
+    let packed = read_byte(reader)?;
+    let bg_color = read_byte(reader)?;
+    let pixel_aspect_ratio = read_byte(reader)?;
+
+    let global_color_table = match color_table_spec(packed) {
+        Some(size) => Some(read_color_table(reader, size)?),
+        None => None,
+    };
+
+    Ok(LogicalScreenDescriptor {
+        width,
+        height,
+        global_color_table,
+        bg_color,
+        pixel_aspect_ratio,
+    })
+}
+
+fn read_header<R: Read>(reader: &mut R) -> Result<Header, DecodingError> {
+    let version = read_version(reader)?;
+    let screen_descriptor = read_logical_screen_descriptor(reader)?;
+
+    Ok(Header {
+        version,
+        screen_descriptor,
+    })
+}
+
+fn read_sub_blocks<R: Read>(reader: &mut R) -> Result<SubBlocks, DecodingError> {
+    let mut blocks = Vec::new();
+
+    loop {
+        let len = read_byte(reader)?;
+        if len == 0 {
+            break;
+        }
+
+        let mut data = vec![0u8; len as usize];
+        reader.read_exact(&mut data)?;
+        blocks.push(SubBlock { data });
+    }
+
+    Ok(blocks)
+}
+
+fn read_image_descriptor<R: Read>(reader: &mut R) -> Result<ImageDescriptor, DecodingError> {
+    let x = read_u16_le(reader)?;
+    let y = read_u16_le(reader)?;
+    let width = read_u16_le(reader)?;
+    let height = read_u16_le(reader)?;
+    let packed = read_byte(reader)?;
+
+    let local_color_table = match color_table_spec(packed) {
+        Some(size) => Some(read_color_table(reader, size)?),
+        None => None,
+    };
+
+    Ok(ImageDescriptor {
+        position: (x, y),
+        width,
+        height,
+        local_color_table,
+    })
+}
+
+fn read_image<R: Read>(reader: &mut R) -> Result<Image, DecodingError> {
+    let image_descriptor = read_image_descriptor(reader)?;
+    let bit_width = read_byte(reader)?;
+    let data = read_sub_blocks(reader)?;
+
+    Ok(Image {
+        image_descriptor,
+        image_data: ImageData { bit_width, data },
+    })
+}
+
+fn read_graphic_control_extension(
+    payload: &[u8],
+) -> Result<GraphicControlExtension, DecodingError> {
+    let [packed, delay_lo, delay_hi, transparent_color_index]: [u8; 4] = payload
+        .try_into()
+        .map_err(|_| DecodingError::Format("graphic control extension payload must be 4 bytes"))?;
+
+    Ok(GraphicControlExtension {
+        disposal_method: ((packed & 0b0001_1100) >> 2).into(),
+        user_input: packed & 0b0000_0010 != 0,
+        transparent_color_flag: packed & 0b0000_0001 != 0,
+        delay_time: u16::from_le_bytes([delay_lo, delay_hi]),
+        transparent_color_index,
+    })
+}
+
+fn read_extension<R: Read>(reader: &mut R) -> Result<Extension, DecodingError> {
+    let label = read_byte(reader)?;
+    let data = read_sub_blocks(reader)?;
+
+    let ext_type = match label {
+        0xF9 => {
+            let payload = data.first().map_or(&[][..], |b| b.data.as_slice());
+            ExtensionType::GraphicControl(read_graphic_control_extension(payload)?)
+        }
+        0xFF => ExtensionType::Application,
+        0xFE => ExtensionType::Comment,
+        0x01 => ExtensionType::PlainText,
+        byte => ExtensionType::Unknown(byte),
+    };
+
+    Ok(Extension { ext_type, data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gif::{DisposalMethod, ImageData};
+
+    #[test]
+    fn decode_header() {
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            b'G', b'I', b'F', b'8', b'9', b'a', // version
+            2, 0, 1, 0, // width, height
+            0b1000_0000, // GCT present, 2 entries
+            0, 0, // bg color, pixel aspect ratio
+            255, 0, 0, // color 0
+            0, 0, 0, // color 1
+        ];
+
+        let decoder = Decoder::new(input).unwrap();
+
+        assert_eq!(Version::Gif89a, decoder.header().version);
+        assert_eq!(2, decoder.header().screen_descriptor.width);
+        assert_eq!(1, decoder.header().screen_descriptor.height);
+        assert_eq!(
+            Some(ColorTable {
+                colors: vec![[255, 0, 0], [0, 0, 0]],
+            }),
+            decoder.header().screen_descriptor.global_color_table
+        );
+    }
+
+    #[test]
+    fn decode_blocks_end_to_end() {
+        // The stream exercised by `gif::tests::gif_round_trip` with a graphic
+        // control extension added, decoded incrementally instead of all at once.
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            b'G', b'I', b'F', b'8', b'9', b'a', // version
+            2, 0, 1, 0, // width, height
+            0b1000_0000, // GCT present, 2 entries
+            0, 0, // bg color, pixel aspect ratio
+            255, 0, 0, // color 0
+            0, 0, 0, // color 1
+            b'!', 0xF9, // graphic control extension
+            4, 0b0000_1001, 0x0A, 0x00, 0x07, // 4-byte sub-block
+            0, // block terminator
+            b',', // image separator
+            0, 0, 0, 0, 2, 0, 1, 0, // position, width, height
+            0, // no local color table
+            2, // LZW min code size
+            2, 0b1000_1100, 0b0010_1101, // a single sub-block
+            0, // block terminator
+            b';', // trailer
+        ];
+
+        let decoder = Decoder::new(input).unwrap();
+        let blocks: Result<Vec<Block>, DecodingError> = decoder.collect();
+        let blocks = blocks.unwrap();
+
+        assert_eq!(
+            vec![
+                Block::Extension(Extension {
+                    ext_type: ExtensionType::GraphicControl(GraphicControlExtension {
+                        disposal_method: DisposalMethod::RestoreBackground,
+                        user_input: false,
+                        transparent_color_flag: true,
+                        delay_time: 10,
+                        transparent_color_index: 7,
+                    }),
+                    data: vec![SubBlock {
+                        data: vec![0b0000_1001, 0x0A, 0x00, 0x07],
+                    }],
+                }),
+                Block::Image(Image {
+                    image_descriptor: ImageDescriptor {
+                        position: (0, 0),
+                        width: 2,
+                        height: 1,
+                        local_color_table: None,
+                    },
+                    image_data: ImageData {
+                        bit_width: 2,
+                        data: vec![SubBlock {
+                            data: vec![0b1000_1100, 0b0010_1101],
+                        }],
+                    },
+                }),
+            ],
+            blocks
+        );
+    }
+
+    #[test]
+    fn decode_truncated_stream_errors_instead_of_panicking() {
+        // Header and a graphic control extension label, but the payload is
+        // cut off partway through.
+        #[rustfmt::skip]
+        let input: &[u8] = &[
+            b'G', b'I', b'F', b'8', b'9', b'a',
+            2, 0, 1, 0,
+            0, // no GCT
+            0, 0,
+            b'!', 0xF9, 4, 0xAA,
+        ];
+
+        let decoder = Decoder::new(input).unwrap();
+        let blocks: Vec<_> = decoder.collect();
+
+        assert_eq!(1, blocks.len());
+        assert!(blocks[0].is_err());
+    }
+}
@@ -2,10 +2,12 @@
 //! <https://en.wikipedia.org/wiki/GIF>
 #![allow(unused)]
 
+use std::collections::HashMap;
+
 use nom::branch::alt;
 use nom::bytes::complete::{is_a, is_not, tag, take, take_till1, take_until1, take_while1};
 use nom::combinator::{map, map_res};
-use nom::multi::{count, many1};
+use nom::multi::{count, many1, many_till};
 use nom::sequence::{pair, separated_pair, terminated, tuple};
 use nom::{bits, IResult, Parser};
 
@@ -16,6 +18,11 @@ where
     fn parse(input: &[u8]) -> IResult<&[u8], Self>;
 }
 
+/// The inverse of [`Parse`]: appends `self`'s byte representation to `out`.
+pub(crate) trait Encode {
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
 macro_rules! le_int_from_bytes {
     ($int:tt, $bytes:expr) => {
         $int::from_le_bytes($bytes.try_into().expect("conversion from le failed"))
@@ -23,7 +30,7 @@ macro_rules! le_int_from_bytes {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum Version {
+pub(crate) enum Version {
     Gif87a,
     Gif89a,
 }
@@ -41,31 +48,61 @@ impl Parse for Version {
     }
 }
 
+impl Encode for Version {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(match self {
+            Self::Gif87a => b"GIF87a",
+            Self::Gif89a => b"GIF89a",
+        });
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
-struct ColorTable {
-    colors: Vec<Vec<u8>>,
+pub(crate) struct ColorTable {
+    pub(crate) colors: Vec<[u8; 3]>,
 }
 
 impl ColorTable {
     fn parse(input: &[u8], size: usize) -> IResult<&[u8], Self> {
-        map(count(take(size), 256), |colors| Self {
-            colors: colors.into_iter().map(|c: &[u8]| c.to_vec()).collect(),
+        map(count(take(3usize), size), |colors: Vec<&[u8]>| Self {
+            colors: colors.into_iter().map(|c| [c[0], c[1], c[2]]).collect(),
         })(input)
     }
 }
 
-/// Highest bit indicates presence, lowest three bits indicate length
-fn color_table_spec(byte: u8) -> Option<usize> {
-    ((byte & 0b10000000) >> 7 == 1).then_some(((byte & 0b00000111) as usize * 255) + 1)
+impl Encode for ColorTable {
+    fn encode(&self, out: &mut Vec<u8>) {
+        for color in &self.colors {
+            out.extend_from_slice(color);
+        }
+    }
+}
+
+/// Highest bit indicates presence, lowest three bits are `N`, where the
+/// color table holds `2^(N+1)` entries.
+pub(crate) fn color_table_spec(byte: u8) -> Option<usize> {
+    ((byte & 0b10000000) >> 7 == 1).then_some(1 << ((byte & 0b00000111) + 1))
+}
+
+/// Inverse of [`color_table_spec`]: the packed presence/size byte for a
+/// color table of this size, or `0` when there is none.
+fn encode_color_table_flag(table: Option<&ColorTable>) -> u8 {
+    match table {
+        Some(table) => {
+            let n = table.colors.len().trailing_zeros() as u8 - 1;
+            0b1000_0000 | n
+        }
+        None => 0,
+    }
 }
 
 #[derive(Debug)]
-struct LogicalScreenDescriptor {
-    width: u16,
-    height: u16,
-    global_color_table: Option<ColorTable>,
-    bg_color: u8,
-    pixel_aspect_ratio: u8,
+pub(crate) struct LogicalScreenDescriptor {
+    pub(crate) width: u16,
+    pub(crate) height: u16,
+    pub(crate) global_color_table: Option<ColorTable>,
+    pub(crate) bg_color: u8,
+    pub(crate) pixel_aspect_ratio: u8,
 }
 
 impl Parse for LogicalScreenDescriptor {
@@ -101,12 +138,26 @@ impl Parse for LogicalScreenDescriptor {
     }
 }
 
+impl Encode for LogicalScreenDescriptor {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.width.to_le_bytes());
+        out.extend_from_slice(&self.height.to_le_bytes());
+        out.push(encode_color_table_flag(self.global_color_table.as_ref()));
+        out.push(self.bg_color);
+        out.push(self.pixel_aspect_ratio);
+
+        if let Some(table) = &self.global_color_table {
+            table.encode(out);
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
-struct ImageDescriptor {
-    position: (u16, u16),
-    width: u16,
-    height: u16,
-    local_color_table: Option<ColorTable>,
+pub(crate) struct ImageDescriptor {
+    pub(crate) position: (u16, u16),
+    pub(crate) width: u16,
+    pub(crate) height: u16,
+    pub(crate) local_color_table: Option<ColorTable>,
 }
 
 impl Parse for ImageDescriptor {
@@ -120,7 +171,7 @@ impl Parse for ImageDescriptor {
         ))(input)
         .and_then(|(rest, (x, y, w, h, lct))| {
             let (rest, local_color_table) = match color_table_spec(lct[0]) {
-                Some(size) => ColorTable::parse(input, size).map(|(r, ct)| (r, Some(ct)))?,
+                Some(size) => ColorTable::parse(rest, size).map(|(r, ct)| (r, Some(ct)))?,
                 None => (rest, None),
             };
 
@@ -136,10 +187,24 @@ impl Parse for ImageDescriptor {
     }
 }
 
+impl Encode for ImageDescriptor {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.position.0.to_le_bytes());
+        out.extend_from_slice(&self.position.1.to_le_bytes());
+        out.extend_from_slice(&self.width.to_le_bytes());
+        out.extend_from_slice(&self.height.to_le_bytes());
+        out.push(encode_color_table_flag(self.local_color_table.as_ref()));
+
+        if let Some(table) = &self.local_color_table {
+            table.encode(out);
+        }
+    }
+}
+
 #[derive(Debug)]
-struct Header {
-    version: Version,
-    screen_descriptor: LogicalScreenDescriptor,
+pub(crate) struct Header {
+    pub(crate) version: Version,
+    pub(crate) screen_descriptor: LogicalScreenDescriptor,
 }
 
 impl Parse for Header {
@@ -154,50 +219,311 @@ impl Parse for Header {
     }
 }
 
+impl Encode for Header {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.version.encode(out);
+        self.screen_descriptor.encode(out);
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
-struct SubBlock {
+pub(crate) struct SubBlock {
     // Should be generated
     // length: u8,
-    data: Vec<u8>,
+    pub(crate) data: Vec<u8>,
     // Null block
     // end: u8,
 }
 
 impl Parse for SubBlock {
     fn parse(input: &[u8]) -> IResult<&[u8], Self> {
-        map(
-            terminated(is_not(b"\0".as_slice()), tag(b"\0")),
-            |data: &[u8]| Self {
-                data: data.to_vec(),
-            },
-        )(input)
+        let (rest, len) = take(1usize)(input)?;
+        map(take(len[0] as usize), |data: &[u8]| Self {
+            data: data.to_vec(),
+        })(rest)
     }
 }
 
-type SubBlocks = Vec<SubBlock>;
+pub(crate) type SubBlocks = Vec<SubBlock>;
+
+/// A run of length-prefixed [`SubBlock`]s terminated by a null (zero-length) block.
+fn sub_blocks(input: &[u8]) -> IResult<&[u8], SubBlocks> {
+    map(
+        many_till(SubBlock::parse, tag(b"\0".as_slice())),
+        |(blocks, _)| blocks,
+    )(input)
+}
+
+/// Writes sub-blocks back out exactly as they were split (each is already
+/// at most 255 bytes), terminated by a null block.
+fn encode_sub_blocks(data: &[SubBlock], out: &mut Vec<u8>) {
+    for block in data {
+        out.push(block.data.len() as u8);
+        out.extend_from_slice(&block.data);
+    }
+    out.push(0);
+}
 
 #[derive(Debug, PartialEq, Eq)]
-struct ImageData {
-    bit_width: u8,
-    data: SubBlocks,
+pub(crate) struct ImageData {
+    pub(crate) bit_width: u8,
+    pub(crate) data: SubBlocks,
 }
 
 impl Parse for ImageData {
     fn parse(input: &[u8]) -> IResult<&[u8], Self> {
-        map(
-            pair(take(1usize), many1(SubBlock::parse)),
-            |(bit_width, data)| Self {
-                bit_width: bit_width[0],
-                data,
-            },
-        )(input)
+        map(pair(take(1usize), sub_blocks), |(bit_width, data)| Self {
+            bit_width: bit_width[0],
+            data,
+        })(input)
+    }
+}
+
+impl Encode for ImageData {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.bit_width);
+        encode_sub_blocks(&self.data, out);
+    }
+}
+
+/// The color-table indices produced by LZW-decompressing an [`ImageData`] block.
+#[derive(Debug, PartialEq, Eq)]
+struct DecodedImage {
+    indices: Vec<u8>,
+}
+
+impl ImageData {
+    /// Concatenate the sub-blocks into one code stream and LZW-decompress it
+    /// into color-table indices.
+    fn decode(&self) -> DecodedImage {
+        let stream: Vec<u8> = self
+            .data
+            .iter()
+            .flat_map(|b| b.data.iter().copied())
+            .collect();
+        DecodedImage {
+            indices: lzw_decode(&stream, self.bit_width),
+        }
+    }
+
+    /// LZW-compress a buffer of color-table indices into an [`ImageData`]
+    /// block, the inverse of [`ImageData::decode`].
+    fn from_indices(indices: &[u8], bit_width: u8) -> Self {
+        let stream = lzw_encode(indices, bit_width);
+        let data = stream
+            .chunks(255)
+            .map(|chunk| SubBlock {
+                data: chunk.to_vec(),
+            })
+            .collect();
+
+        Self { bit_width, data }
+    }
+}
+
+/// Reads variable-width, LSB-first codes out of a byte stream.
+struct BitReader<'a> {
+    data: &'a [u8],
+    byte_pos: usize,
+    bit_pos: u8,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            byte_pos: 0,
+            bit_pos: 0,
+        }
+    }
+
+    fn read(&mut self, width: u8) -> Option<u16> {
+        let mut code: u16 = 0;
+
+        for i in 0..width {
+            let byte = *self.data.get(self.byte_pos)?;
+            let bit = (byte >> self.bit_pos) & 1;
+            code |= u16::from(bit) << i;
+
+            self.bit_pos += 1;
+            if self.bit_pos == 8 {
+                self.bit_pos = 0;
+                self.byte_pos += 1;
+            }
+        }
+
+        Some(code)
+    }
+}
+
+/// Seeds a fresh LZW dictionary: single-index entries `0..clear`, followed by
+/// placeholders for the clear and end-of-information codes.
+fn reset_dictionary(clear_code: u16) -> Vec<Vec<u8>> {
+    let mut dict: Vec<Vec<u8>> = (0..clear_code).map(|i| vec![i as u8]).collect();
+    dict.push(Vec::new()); // clear code
+    dict.push(Vec::new()); // end-of-information code
+    dict
+}
+
+/// The GIF variant of LZW decompression: variable-width codes, LSB-first,
+/// with a clear code that resets the dictionary and an end code that stops
+/// decoding.
+fn lzw_decode(stream: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear_code = 1u16 << min_code_size;
+    let end_code = clear_code + 1;
+    let min_code_width = min_code_size + 1;
+
+    let mut reader = BitReader::new(stream);
+    let mut dict = reset_dictionary(clear_code);
+    let mut code_width = min_code_width;
+    let mut prev: Option<Vec<u8>> = None;
+    let mut output = Vec::new();
+
+    while let Some(code) = reader.read(code_width) {
+        if code == clear_code {
+            dict = reset_dictionary(clear_code);
+            code_width = min_code_width;
+            prev = None;
+            continue;
+        }
+
+        if code == end_code {
+            break;
+        }
+
+        let entry = match dict.get(code as usize) {
+            Some(entry) => entry.clone(),
+            None if code as usize == dict.len() => {
+                let Some(prev) = &prev else { break };
+                let mut entry = prev.clone();
+                entry.push(prev[0]);
+                entry
+            }
+            None => break,
+        };
+
+        output.extend_from_slice(&entry);
+
+        if let Some(prev) = prev {
+            let mut new_entry = prev;
+            new_entry.push(entry[0]);
+            dict.push(new_entry);
+
+            if dict.len() == 1 << code_width && code_width < 12 {
+                code_width += 1;
+            }
+        }
+
+        prev = Some(entry);
+    }
+
+    output
+}
+
+/// Writes variable-width codes into a byte stream, LSB-first, the inverse of
+/// [`BitReader`].
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit_pos: u8,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            bit_pos: 0,
+        }
+    }
+
+    fn write(&mut self, code: u16, width: u8) {
+        for i in 0..width {
+            if self.bit_pos == 0 {
+                self.bytes.push(0);
+            }
+
+            let bit = ((code >> i) & 1) as u8;
+            *self.bytes.last_mut().expect("just pushed a byte") |= bit << self.bit_pos;
+
+            self.bit_pos = (self.bit_pos + 1) % 8;
+        }
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Seeds a fresh LZW encoding dictionary mapping single-index sequences
+/// `0..clear` to their codes.
+fn reset_encode_dictionary(clear_code: u16) -> HashMap<Vec<u8>, u16> {
+    (0..clear_code).map(|i| (vec![i as u8], i)).collect()
+}
+
+/// The inverse of [`lzw_decode`]: builds the string table incrementally as
+/// it walks the indices, emitting a code each time the current run plus the
+/// next index falls out of the dictionary, and growing the code width in
+/// step with the decoder.
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear_code = 1u16 << min_code_size;
+    let end_code = clear_code + 1;
+    let min_code_width = min_code_size + 1;
+    let max_dict_size = 1usize << 12;
+
+    let mut writer = BitWriter::new();
+    writer.write(clear_code, min_code_width);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        writer.write(end_code, min_code_width);
+        return writer.into_bytes();
+    };
+
+    let mut dict = reset_encode_dictionary(clear_code);
+    let mut next_code = end_code + 1;
+    let mut code_width = min_code_width;
+    let mut current = vec![first];
+
+    for &index in rest {
+        let mut candidate = current.clone();
+        candidate.push(index);
+
+        if dict.contains_key(&candidate) {
+            current = candidate;
+            continue;
+        }
+
+        writer.write(dict[&current], code_width);
+
+        if next_code as usize >= max_dict_size {
+            writer.write(clear_code, code_width);
+            dict = reset_encode_dictionary(clear_code);
+            next_code = end_code + 1;
+            code_width = min_code_width;
+        } else {
+            dict.insert(candidate, next_code);
+            next_code += 1;
+
+            // The decoder can't add its own entry for the very first code
+            // after a clear (it takes two codes to know what to append), so
+            // its table permanently trails this one by one entry. Grow a
+            // code late to stay in step with where the decoder grows.
+            if next_code as usize == (1 << code_width) + 1 && code_width < 12 {
+                code_width += 1;
+            }
+        }
+
+        current = vec![index];
     }
+
+    writer.write(dict[&current], code_width);
+    writer.write(end_code, code_width);
+
+    writer.into_bytes()
 }
 
 #[derive(Debug, PartialEq, Eq)]
-struct Image {
-    image_descriptor: ImageDescriptor,
-    image_data: ImageData,
+pub(crate) struct Image {
+    pub(crate) image_descriptor: ImageDescriptor,
+    pub(crate) image_data: ImageData,
 }
 
 impl Parse for Image {
@@ -212,41 +538,150 @@ impl Parse for Image {
     }
 }
 
+impl Encode for Image {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.image_descriptor.encode(out);
+        self.image_data.encode(out);
+    }
+}
+
+/// What happens to the previous frame before rendering the next one, stored
+/// in bits 2-4 of a [`GraphicControlExtension`]'s packed byte.
 #[derive(Debug, PartialEq, Eq)]
-enum ExtensionType {
-    GraphicControl,
-    // TODO store byte
-    Unknown,
+pub(crate) enum DisposalMethod {
+    None,
+    DoNotDispose,
+    RestoreBackground,
+    RestorePrevious,
 }
 
-impl From<u8> for ExtensionType {
-    fn from(byte: u8) -> Self {
-        match byte {
-            _ => Self::Unknown,
+impl From<u8> for DisposalMethod {
+    fn from(bits: u8) -> Self {
+        match bits {
+            1 => Self::DoNotDispose,
+            2 => Self::RestoreBackground,
+            3 => Self::RestorePrevious,
+            _ => Self::None,
         }
     }
 }
 
+/// The typed payload of a Graphic Control Extension (label `0xF9`): frame
+/// timing, disposal, and transparency.
 #[derive(Debug, PartialEq, Eq)]
-struct Extension {
-    ext_type: ExtensionType,
-    data: SubBlocks,
+pub(crate) struct GraphicControlExtension {
+    pub(crate) disposal_method: DisposalMethod,
+    pub(crate) user_input: bool,
+    pub(crate) transparent_color_flag: bool,
+    pub(crate) delay_time: u16,
+    pub(crate) transparent_color_index: u8,
 }
 
-impl Parse for Extension {
+impl Parse for GraphicControlExtension {
     fn parse(input: &[u8]) -> IResult<&[u8], Self> {
         map(
-            pair(take(1usize), many1(SubBlock::parse)),
-            |(type_byte, data)| Self {
-                ext_type: type_byte[0].into(),
-                data,
+            tuple((take(1usize), take(2usize), take(1usize))),
+            |(packed, delay, index): (&[u8], &[u8], &[u8])| {
+                let packed = packed[0];
+                Self {
+                    disposal_method: ((packed & 0b0001_1100) >> 2).into(),
+                    user_input: packed & 0b0000_0010 != 0,
+                    transparent_color_flag: packed & 0b0000_0001 != 0,
+                    delay_time: le_int_from_bytes!(u16, delay),
+                    transparent_color_index: index[0],
+                }
+            },
+        )(input)
+    }
+}
+
+impl Encode for GraphicControlExtension {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let disposal_bits: u8 = match self.disposal_method {
+            DisposalMethod::None => 0,
+            DisposalMethod::DoNotDispose => 1,
+            DisposalMethod::RestoreBackground => 2,
+            DisposalMethod::RestorePrevious => 3,
+        };
+        let packed = (disposal_bits << 2)
+            | (u8::from(self.user_input) << 1)
+            | u8::from(self.transparent_color_flag);
+
+        out.push(packed);
+        out.extend_from_slice(&self.delay_time.to_le_bytes());
+        out.push(self.transparent_color_index);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ExtensionType {
+    GraphicControl(GraphicControlExtension),
+    /// `0xFF`, e.g. the NETSCAPE looping block.
+    Application,
+    /// `0xFE`.
+    Comment,
+    /// `0x01`.
+    PlainText,
+    Unknown(u8),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Extension {
+    pub(crate) ext_type: ExtensionType,
+    pub(crate) data: SubBlocks,
+}
+
+impl Parse for Extension {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map_res(
+            pair(take(1usize), sub_blocks),
+            |(label, data): (&[u8], SubBlocks)| {
+                let ext_type = match label[0] {
+                    0xF9 => {
+                        let payload = data.first().map_or(&[][..], |b| b.data.as_slice());
+                        let (_, gce) = GraphicControlExtension::parse(payload)
+                            .map_err(|_| "graphic control extension payload malformed")?;
+                        ExtensionType::GraphicControl(gce)
+                    }
+                    0xFF => ExtensionType::Application,
+                    0xFE => ExtensionType::Comment,
+                    0x01 => ExtensionType::PlainText,
+                    byte => ExtensionType::Unknown(byte),
+                };
+
+                Ok::<_, &'static str>(Self { ext_type, data })
             },
         )(input)
     }
 }
 
+/// Inverse of the label match in [`Extension::parse`].
+fn extension_label(ext_type: &ExtensionType) -> u8 {
+    match ext_type {
+        ExtensionType::GraphicControl(_) => 0xF9,
+        ExtensionType::Application => 0xFF,
+        ExtensionType::Comment => 0xFE,
+        ExtensionType::PlainText => 0x01,
+        ExtensionType::Unknown(byte) => *byte,
+    }
+}
+
+impl Encode for Extension {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(extension_label(&self.ext_type));
+
+        if let ExtensionType::GraphicControl(gce) = &self.ext_type {
+            let mut payload = Vec::new();
+            gce.encode(&mut payload);
+            encode_sub_blocks(&[SubBlock { data: payload }], out);
+        } else {
+            encode_sub_blocks(&self.data, out);
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
-enum Block {
+pub(crate) enum Block {
     Image(Image),
     Extension(Extension),
 }
@@ -276,6 +711,17 @@ impl Block {
     }
 }
 
+impl Encode for Block {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.sentinel());
+
+        match self {
+            Self::Image(image) => image.encode(out),
+            Self::Extension(extension) => extension.encode(out),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Gif {
     header: Header,
@@ -291,6 +737,64 @@ impl Parse for Gif {
     }
 }
 
+impl Encode for Gif {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.header.encode(out);
+
+        for block in &self.blocks {
+            block.encode(out);
+        }
+
+        out.push(b';');
+    }
+}
+
+/// A single decoded image block: its dimensions, color-table indices, and
+/// the palette to resolve them against.
+pub(crate) struct Frame<'a> {
+    pub(crate) width: u16,
+    pub(crate) height: u16,
+    pub(crate) indices: Vec<u8>,
+    pub(crate) palette: &'a [[u8; 3]],
+}
+
+impl Gif {
+    /// LZW-decode every image block into a renderable frame, resolving each
+    /// one's palette from its local color table and falling back to the
+    /// global color table. Image blocks with neither table are skipped.
+    pub(crate) fn frames(&self) -> Vec<Frame<'_>> {
+        let global_palette = self
+            .header
+            .screen_descriptor
+            .global_color_table
+            .as_ref()
+            .map(|ct| ct.colors.as_slice());
+
+        self.blocks
+            .iter()
+            .filter_map(|block| match block {
+                Block::Image(image) => Some(image),
+                Block::Extension(_) => None,
+            })
+            .filter_map(|image| {
+                let palette = image
+                    .image_descriptor
+                    .local_color_table
+                    .as_ref()
+                    .map(|ct| ct.colors.as_slice())
+                    .or(global_palette)?;
+
+                Some(Frame {
+                    width: image.image_descriptor.width,
+                    height: image.image_descriptor.height,
+                    indices: image.image_data.decode().indices,
+                    palette,
+                })
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -308,15 +812,42 @@ mod tests {
             SubBlock {
                 data: vec![b'a', b'b', b'c']
             },
-            SubBlock::parse(b"abc\0").unwrap().1
+            SubBlock::parse(&[3, b'a', b'b', b'c']).unwrap().1
+        );
+    }
+
+    #[test]
+    fn decode_image_data() {
+        // The classic "TOOT-TOOT" LZW example from the GIF LZW literature.
+        let image_data = ImageData {
+            bit_width: 2,
+            data: vec![SubBlock {
+                data: vec![0x8C, 0x2D, 0x99, 0x87, 0x2A],
+            }],
+        };
+
+        assert_eq!(
+            DecodedImage {
+                indices: vec![1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2]
+            },
+            image_data.decode()
         );
     }
 
     #[test]
     fn parse_extension_block() {
+        let mut input = vec![b'!', b'2'];
+        input.push(9);
+        input.extend_from_slice(b"f7eyf8e7y");
+        input.push(6);
+        input.extend_from_slice(b"h3872h");
+        input.push(6);
+        input.extend_from_slice(b"he2187");
+        input.push(0);
+
         assert_eq!(
             Block::Extension(Extension {
-                ext_type: ExtensionType::Unknown,
+                ext_type: ExtensionType::Unknown(b'2'),
                 data: vec![
                     SubBlock {
                         data: b"f7eyf8e7y".as_slice().to_vec(),
@@ -329,7 +860,86 @@ mod tests {
                     },
                 ]
             }),
-            Block::parse(b"!2f7eyf8e7y\0h3872h\0he2187\0").unwrap().1
+            Block::parse(&input).unwrap().1
         );
     }
+
+    #[test]
+    fn parse_graphic_control_extension() {
+        // label 0xF9, a 4-byte sub-block (packed byte, delay_time LE, transparent index), then terminator.
+        let input = [0xF9, 4, 0b0000_1001, 0x0A, 0x00, 0x07, 0x00];
+
+        assert_eq!(
+            Extension {
+                ext_type: ExtensionType::GraphicControl(GraphicControlExtension {
+                    disposal_method: DisposalMethod::RestoreBackground,
+                    user_input: false,
+                    transparent_color_flag: true,
+                    delay_time: 10,
+                    transparent_color_index: 7,
+                }),
+                data: vec![SubBlock {
+                    data: vec![0b0000_1001, 0x0A, 0x00, 0x07]
+                }],
+            },
+            Extension::parse(&input).unwrap().1
+        );
+    }
+
+    #[test]
+    fn parse_graphic_control_extension_malformed_payload_errors() {
+        // label 0xF9, but a 2-byte sub-block instead of the required 4 bytes.
+        let input = [0xF9, 2, 0xAA, 0xBB, 0x00];
+
+        assert!(Extension::parse(&input).is_err());
+    }
+
+    #[test]
+    fn lzw_round_trip() {
+        let indices = vec![1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2];
+        let bit_width = 2;
+
+        let image_data = ImageData::from_indices(&indices, bit_width);
+
+        assert_eq!(DecodedImage { indices }, image_data.decode());
+    }
+
+    #[test]
+    fn color_table_round_trip() {
+        let table = ColorTable {
+            colors: vec![[255, 0, 0], [0, 255, 0], [0, 0, 255], [0, 0, 0]],
+        };
+
+        let mut bytes = Vec::new();
+        table.encode(&mut bytes);
+
+        assert_eq!(table, ColorTable::parse(&bytes, 4).unwrap().1);
+    }
+
+    #[test]
+    fn gif_round_trip() {
+        #[rustfmt::skip]
+        let input = [
+            b'G', b'I', b'F', b'8', b'9', b'a', // version
+            2, 0, 1, 0, // width, height
+            0b1000_0000, // GCT present, 2 entries
+            0, 0, // bg color, pixel aspect ratio
+            255, 0, 0, // color 0
+            0, 0, 0, // color 1
+            b',', // image separator
+            0, 0, 0, 0, 2, 0, 1, 0, // position, width, height
+            0, // no local color table
+            2, // LZW min code size
+            2, 0b1000_1100, 0b0010_1101, // a single sub-block
+            0, // block terminator
+            b';', // trailer
+        ];
+
+        let gif = Gif::parse(&input).unwrap().1;
+
+        let mut encoded = Vec::new();
+        gif.encode(&mut encoded);
+
+        assert_eq!(input.as_slice(), encoded);
+    }
 }
@@ -1,18 +1,34 @@
 mod bmp;
+mod decoder;
 mod gif;
+mod qoi;
+mod render;
 
 use crate::gif::{Gif, Parse};
+use crate::render::{render, RenderMode};
 
 fn main() {
     let bytes = include_bytes!("../GifSample.gif");
+    let (_, gif) = Gif::parse(bytes).expect("Parse failed");
 
-    for (i, byte) in bytes.iter().enumerate() {
-        println!("{1:3X}: HEX: {0:02X} DEC: {0:3}", byte, i);
-    }
+    let mode = if supports_truecolor() {
+        RenderMode::Truecolor
+    } else {
+        RenderMode::Ascii
+    };
 
-    println!("Parsing...");
+    for frame in gif.frames() {
+        print!("{}", render(&frame, terminal_width(), mode));
+    }
+}
 
-    let gif = Gif::parse(bytes).expect("Parse failed");
+fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .unwrap_or(80)
+}
 
-    println!("{:#?}", gif);
+fn supports_truecolor() -> bool {
+    std::env::var("COLORTERM").is_ok_and(|v| v.contains("truecolor") || v.contains("24bit"))
 }
@@ -0,0 +1,386 @@
+//! QOI ("Quite OK Image") format
+//! <https://qoiformat.org/qoi-specification.pdf>
+#![allow(unused)]
+
+use nom::bytes::complete::{tag, take};
+use nom::combinator::map;
+use nom::number::complete::{be_u32, u8 as parse_u8};
+use nom::sequence::tuple;
+use nom::IResult;
+
+use crate::gif::{Encode, Parse};
+
+const MAGIC: &[u8; 4] = b"qoif";
+const END_MARKER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+const OP_RGB: u8 = 0xFE;
+const OP_RGBA: u8 = 0xFF;
+
+#[derive(Debug, PartialEq, Eq)]
+struct QoiHeader {
+    width: u32,
+    height: u32,
+    channels: u8,
+    colorspace: u8,
+}
+
+impl Parse for QoiHeader {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        map(
+            tuple((tag(MAGIC.as_slice()), be_u32, be_u32, parse_u8, parse_u8)),
+            |(_, width, height, channels, colorspace)| Self {
+                width,
+                height,
+                channels,
+                colorspace,
+            },
+        )(input)
+    }
+}
+
+impl Encode for QoiHeader {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&self.width.to_be_bytes());
+        out.extend_from_slice(&self.height.to_be_bytes());
+        out.push(self.channels);
+        out.push(self.colorspace);
+    }
+}
+
+/// One entry in the chunk stream, already split out of its tag byte(s) but
+/// not yet resolved against the running pixel array (see [`Qoi::decode`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Chunk {
+    Rgb([u8; 3]),
+    Rgba([u8; 4]),
+    /// Index into the 64-entry array of previously seen pixels.
+    Index(u8),
+    /// Per-channel diffs from the previous pixel, each biased by 2.
+    Diff(u8, u8, u8),
+    /// A green diff biased by 32, and red/blue diffs from green biased by 8.
+    Luma(u8, u8, u8),
+    /// Repeat the previous pixel; holds the run length minus 1.
+    Run(u8),
+}
+
+impl Parse for Chunk {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (rest, tag_byte) = take(1usize)(input)?;
+        let tag_byte = tag_byte[0];
+
+        match tag_byte {
+            OP_RGB => map(take(3usize), |b: &[u8]| Self::Rgb([b[0], b[1], b[2]]))(rest),
+            OP_RGBA => map(take(4usize), |b: &[u8]| {
+                Self::Rgba([b[0], b[1], b[2], b[3]])
+            })(rest),
+            _ => match tag_byte >> 6 {
+                0b00 => Ok((rest, Self::Index(tag_byte & 0b0011_1111))),
+                0b01 => Ok((
+                    rest,
+                    Self::Diff(
+                        (tag_byte >> 4) & 0b11,
+                        (tag_byte >> 2) & 0b11,
+                        tag_byte & 0b11,
+                    ),
+                )),
+                0b10 => {
+                    let (rest, byte2) = take(1usize)(rest)?;
+                    let byte2 = byte2[0];
+                    Ok((
+                        rest,
+                        Self::Luma(tag_byte & 0b0011_1111, byte2 >> 4, byte2 & 0b1111),
+                    ))
+                }
+                _ => Ok((rest, Self::Run(tag_byte & 0b0011_1111))),
+            },
+        }
+    }
+}
+
+impl Encode for Chunk {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match *self {
+            Self::Rgb(rgb) => {
+                out.push(OP_RGB);
+                out.extend_from_slice(&rgb);
+            }
+            Self::Rgba(rgba) => {
+                out.push(OP_RGBA);
+                out.extend_from_slice(&rgba);
+            }
+            Self::Index(index) => out.push(index & 0b0011_1111),
+            Self::Diff(dr, dg, db) => out.push(0b0100_0000 | (dr << 4) | (dg << 2) | db),
+            Self::Luma(dg, dr_dg, db_dg) => {
+                out.push(0b1000_0000 | dg);
+                out.push((dr_dg << 4) | db_dg);
+            }
+            Self::Run(len) => out.push(0b1100_0000 | len),
+        }
+    }
+}
+
+/// Parses chunks until they account for `total_pixels`, since the chunk
+/// stream carries no explicit count of its own.
+fn parse_chunks(mut input: &[u8], total_pixels: usize) -> IResult<&[u8], Vec<Chunk>> {
+    let mut chunks = Vec::new();
+    let mut pixels_seen = 0;
+
+    while pixels_seen < total_pixels {
+        let (rest, chunk) = Chunk::parse(input)?;
+        pixels_seen += match chunk {
+            Chunk::Run(len) => usize::from(len) + 1,
+            _ => 1,
+        };
+        chunks.push(chunk);
+        input = rest;
+    }
+
+    Ok((input, chunks))
+}
+
+#[derive(Debug)]
+pub struct Qoi {
+    header: QoiHeader,
+    chunks: Vec<Chunk>,
+}
+
+impl Parse for Qoi {
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (rest, header) = QoiHeader::parse(input)?;
+        let total_pixels = header.width as usize * header.height as usize;
+
+        let (rest, chunks) = parse_chunks(rest, total_pixels)?;
+        let (rest, _) = tag(END_MARKER.as_slice())(rest)?;
+
+        Ok((rest, Self { header, chunks }))
+    }
+}
+
+impl Encode for Qoi {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.header.encode(out);
+
+        for chunk in &self.chunks {
+            chunk.encode(out);
+        }
+
+        out.extend_from_slice(&END_MARKER);
+    }
+}
+
+/// `(r*3 + g*5 + b*7 + a*11) % 64`, the slot a pixel occupies in the running
+/// array of previously seen pixels.
+fn qoi_hash(pixel: [u8; 4]) -> u8 {
+    let [r, g, b, a] = pixel;
+    r.wrapping_mul(3)
+        .wrapping_add(g.wrapping_mul(5))
+        .wrapping_add(b.wrapping_mul(7))
+        .wrapping_add(a.wrapping_mul(11))
+        % 64
+}
+
+/// `QOI_OP_DIFF` only covers per-channel diffs in `-2..=1`.
+fn diff_chunk(dr: u8, dg: u8, db: u8) -> Option<Chunk> {
+    let in_range = |d: u8| matches!(d as i8, -2..=1);
+    (in_range(dr) && in_range(dg) && in_range(db))
+        .then(|| Chunk::Diff(dr.wrapping_add(2), dg.wrapping_add(2), db.wrapping_add(2)))
+}
+
+/// `QOI_OP_LUMA` covers a wider green diff (`-32..=31`) by encoding red and
+/// blue relative to it (`-8..=7`), for images that shift hue gradually.
+fn luma_chunk(dr: u8, dg: u8, db: u8) -> Option<Chunk> {
+    let dr_dg = dr.wrapping_sub(dg);
+    let db_dg = db.wrapping_sub(dg);
+
+    let in_green_range = matches!(dg as i8, -32..=31);
+    let in_cross_range = |d: u8| matches!(d as i8, -8..=7);
+
+    (in_green_range && in_cross_range(dr_dg) && in_cross_range(db_dg)).then(|| {
+        Chunk::Luma(
+            dg.wrapping_add(32),
+            dr_dg.wrapping_add(8),
+            db_dg.wrapping_add(8),
+        )
+    })
+}
+
+impl Qoi {
+    /// Replay the chunk stream against the running 64-entry pixel array into
+    /// a flat RGBA buffer.
+    pub(crate) fn decode(&self) -> Vec<[u8; 4]> {
+        let mut seen = [[0u8; 4]; 64];
+        let mut prev = [0, 0, 0, 255];
+        let mut pixels = Vec::new();
+
+        for chunk in &self.chunks {
+            match *chunk {
+                Chunk::Rgb([r, g, b]) => prev = [r, g, b, prev[3]],
+                Chunk::Rgba(rgba) => prev = rgba,
+                Chunk::Index(index) => {
+                    prev = seen[index as usize];
+                    pixels.push(prev);
+                    continue;
+                }
+                Chunk::Diff(dr, dg, db) => {
+                    let [r, g, b, a] = prev;
+                    prev = [
+                        r.wrapping_add(dr).wrapping_sub(2),
+                        g.wrapping_add(dg).wrapping_sub(2),
+                        b.wrapping_add(db).wrapping_sub(2),
+                        a,
+                    ];
+                }
+                Chunk::Luma(dg, dr_dg, db_dg) => {
+                    let [r, g, b, a] = prev;
+                    let dg = dg.wrapping_sub(32);
+                    let dr = dg.wrapping_add(dr_dg).wrapping_sub(8);
+                    let db = dg.wrapping_add(db_dg).wrapping_sub(8);
+                    prev = [
+                        r.wrapping_add(dr),
+                        g.wrapping_add(dg),
+                        b.wrapping_add(db),
+                        a,
+                    ];
+                }
+                Chunk::Run(len) => {
+                    for _ in 0..=len {
+                        pixels.push(prev);
+                    }
+                    continue;
+                }
+            }
+
+            seen[qoi_hash(prev) as usize] = prev;
+            pixels.push(prev);
+        }
+
+        pixels
+    }
+
+    /// Build a [`Qoi`] image from a flat RGBA buffer, the inverse of
+    /// [`Qoi::decode`].
+    pub(crate) fn from_pixels(
+        pixels: &[[u8; 4]],
+        width: u32,
+        height: u32,
+        channels: u8,
+        colorspace: u8,
+    ) -> Self {
+        let mut seen = [[0u8; 4]; 64];
+        let mut prev = [0, 0, 0, 255];
+        let mut chunks = Vec::new();
+        let mut run = 0u8;
+
+        for &pixel in pixels {
+            if pixel == prev {
+                run += 1;
+                if run == 62 {
+                    chunks.push(Chunk::Run(run - 1));
+                    run = 0;
+                }
+                continue;
+            }
+
+            if run > 0 {
+                chunks.push(Chunk::Run(run - 1));
+                run = 0;
+            }
+
+            let index = qoi_hash(pixel) as usize;
+            if seen[index] == pixel {
+                chunks.push(Chunk::Index(index as u8));
+            } else {
+                seen[index] = pixel;
+
+                let [r, g, b, a] = pixel;
+                let [pr, pg, pb, pa] = prev;
+
+                if a == pa {
+                    let dr = r.wrapping_sub(pr);
+                    let dg = g.wrapping_sub(pg);
+                    let db = b.wrapping_sub(pb);
+
+                    let chunk = diff_chunk(dr, dg, db)
+                        .or_else(|| luma_chunk(dr, dg, db))
+                        .unwrap_or(Chunk::Rgb([r, g, b]));
+                    chunks.push(chunk);
+                } else {
+                    chunks.push(Chunk::Rgba(pixel));
+                }
+            }
+
+            prev = pixel;
+        }
+
+        if run > 0 {
+            chunks.push(Chunk::Run(run - 1));
+        }
+
+        Self {
+            header: QoiHeader {
+                width,
+                height,
+                channels,
+                colorspace,
+            },
+            chunks,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_header() {
+        let mut input = MAGIC.to_vec();
+        input.extend_from_slice(&4u32.to_be_bytes());
+        input.extend_from_slice(&2u32.to_be_bytes());
+        input.push(4);
+        input.push(0);
+
+        assert_eq!(
+            QoiHeader {
+                width: 4,
+                height: 2,
+                channels: 4,
+                colorspace: 0,
+            },
+            QoiHeader::parse(&input).unwrap().1
+        );
+    }
+
+    #[test]
+    fn solid_color_round_trip() {
+        let pixels = vec![[10, 20, 30, 255]; 10];
+
+        let qoi = Qoi::from_pixels(&pixels, 5, 2, 3, 0);
+
+        assert_eq!(pixels, qoi.decode());
+    }
+
+    #[test]
+    fn qoi_round_trip() {
+        // Exercises RGB (a novel color), INDEX (a repeat seen earlier but
+        // not immediately prior), DIFF (a small nudge), LUMA (a wider green
+        // shift), and RGBA (an alpha change) in one pass.
+        let pixels = vec![
+            [10, 20, 30, 255],
+            [8, 19, 29, 255],    // DIFF from the previous pixel
+            [10, 20, 30, 255],   // INDEX back to the first color
+            [28, 40, 45, 255],   // LUMA: a big green jump, small red/blue drift
+            [200, 100, 50, 128], // RGBA: alpha changed
+        ];
+
+        let qoi = Qoi::from_pixels(&pixels, 5, 1, 4, 0);
+
+        let mut encoded = Vec::new();
+        qoi.encode(&mut encoded);
+
+        let parsed = Qoi::parse(&encoded).unwrap().1;
+
+        assert_eq!(pixels, parsed.decode());
+    }
+}
@@ -0,0 +1,174 @@
+//! Terminal preview of decoded GIF frames
+#![allow(unused)]
+
+use crate::gif::Frame;
+
+/// Characters used by the color-less fallback, dimmest to brightest.
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+#[derive(Clone, Copy)]
+pub(crate) enum RenderMode {
+    Truecolor,
+    Ascii,
+}
+
+/// Render a single frame to a string, downscaling (nearest-neighbor) to fit
+/// within `max_width` columns.
+pub(crate) fn render(frame: &Frame, max_width: usize, mode: RenderMode) -> String {
+    let (width, height, pixels) = downscale(frame, max_width);
+
+    match mode {
+        RenderMode::Truecolor => render_truecolor(&pixels, width, height),
+        RenderMode::Ascii => render_ascii(&pixels, width, height),
+    }
+}
+
+fn downscale(frame: &Frame, max_width: usize) -> (usize, usize, Vec<[u8; 3]>) {
+    let src_width = frame.width as usize;
+    let src_height = frame.height as usize;
+
+    if src_width == 0 || src_height == 0 {
+        return (0, 0, Vec::new());
+    }
+
+    let dst_width = src_width.min(max_width.max(1));
+    let scale = src_width as f64 / dst_width as f64;
+    let dst_height = ((src_height as f64 / scale).round() as usize).max(1);
+
+    let mut pixels = Vec::with_capacity(dst_width * dst_height);
+    for y in 0..dst_height {
+        let src_y = ((y as f64 * scale) as usize).min(src_height - 1);
+        for x in 0..dst_width {
+            let src_x = ((x as f64 * scale) as usize).min(src_width - 1);
+            // LZW data can end early (e.g. a truncated/malformed stream), leaving
+            // `indices` shorter than `width * height`; fall back to index 0 rather
+            // than panicking.
+            let index = frame
+                .indices
+                .get(src_y * src_width + src_x)
+                .copied()
+                .unwrap_or(0);
+            let color = frame
+                .palette
+                .get(index as usize)
+                .copied()
+                .unwrap_or([0, 0, 0]);
+            pixels.push(color);
+        }
+    }
+
+    (dst_width, dst_height, pixels)
+}
+
+/// Two source rows share one line via the upper-half-block character, since
+/// terminal cells are roughly twice as tall as they are wide.
+fn render_truecolor(pixels: &[[u8; 3]], width: usize, height: usize) -> String {
+    let mut out = String::new();
+
+    for y in (0..height).step_by(2) {
+        for x in 0..width {
+            let [r1, g1, b1] = pixels[y * width + x];
+            out.push_str(&format!("\x1b[38;2;{r1};{g1};{b1}m"));
+
+            let row2 = y + 1;
+            if row2 < height {
+                let [r2, g2, b2] = pixels[row2 * width + x];
+                out.push_str(&format!("\x1b[48;2;{r2};{g2};{b2}m"));
+            }
+
+            out.push('▀');
+        }
+        out.push_str("\x1b[0m\n");
+    }
+
+    out
+}
+
+fn render_ascii(pixels: &[[u8; 3]], width: usize, height: usize) -> String {
+    let mut out = String::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            let [r, g, b] = pixels[y * width + x];
+            let luminance = 0.299 * f64::from(r) + 0.587 * f64::from(g) + 0.114 * f64::from(b);
+            let ramp_index = ((luminance / 255.0) * (ASCII_RAMP.len() - 1) as f64).round() as usize;
+            out.push(ASCII_RAMP[ramp_index] as char);
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PALETTE: [[u8; 3]; 2] = [[10, 20, 30], [200, 100, 0]];
+
+    #[test]
+    fn downscale_full_frame() {
+        let frame = Frame {
+            width: 2,
+            height: 2,
+            indices: vec![0, 1, 1, 0],
+            palette: &PALETTE,
+        };
+
+        let (width, height, pixels) = downscale(&frame, 2);
+
+        assert_eq!((width, height), (2, 2));
+        assert_eq!(pixels, vec![PALETTE[0], PALETTE[1], PALETTE[1], PALETTE[0]]);
+    }
+
+    #[test]
+    fn downscale_short_index_buffer_does_not_panic() {
+        // A truncated/malformed LZW stream can end before filling `width * height`
+        // indices; downscaling should fall back to a default pixel instead of
+        // indexing out of bounds.
+        let frame = Frame {
+            width: 4,
+            height: 4,
+            indices: vec![0, 1, 0, 1],
+            palette: &PALETTE,
+        };
+
+        let (width, height, pixels) = downscale(&frame, 4);
+
+        assert_eq!((width, height), (4, 4));
+        assert_eq!(pixels.len(), 16);
+        assert_eq!(
+            &pixels[..4],
+            &[PALETTE[0], PALETTE[1], PALETTE[0], PALETTE[1]]
+        );
+        // Out-of-range indices fall back to index 0 rather than panicking.
+        assert_eq!(pixels[4], PALETTE[0]);
+    }
+
+    #[test]
+    fn downscale_empty_palette_does_not_panic() {
+        let frame = Frame {
+            width: 1,
+            height: 1,
+            indices: vec![5],
+            palette: &[],
+        };
+
+        let (_, _, pixels) = downscale(&frame, 1);
+
+        assert_eq!(pixels, vec![[0, 0, 0]]);
+    }
+
+    #[test]
+    fn render_ascii_and_truecolor_smoke() {
+        let frame = Frame {
+            width: 1,
+            height: 2,
+            indices: vec![0, 1],
+            palette: &PALETTE,
+        };
+
+        assert!(!render(&frame, 1, RenderMode::Truecolor).is_empty());
+        assert!(!render(&frame, 1, RenderMode::Ascii).is_empty());
+    }
+}